@@ -6,6 +6,7 @@
 //! - **Results**: Helper functions for creating `CallToolResult` responses
 //! - **Errors**: Traits for converting errors to MCP-compatible format
 //! - **Embeddable**: [`EmbeddableMcp`] trait for in-process execution
+//! - **Migrations**: Versioned SQLite schema migrations (`sqlite` feature)
 //!
 //! # Example
 //!
@@ -47,6 +48,9 @@ pub mod imaging;
 #[cfg(feature = "image-processing")]
 pub mod encoding;
 
+#[cfg(feature = "sqlite")]
+pub mod migrations;
+
 // Re-export commonly used items at crate root
 pub use embeddable::{EmbeddableError, EmbeddableMcp, EmbeddableResult};
 pub use error::{internal_error, invalid_params, IntoMcpError, McpResult, ResultExt};
@@ -0,0 +1,544 @@
+//! Versioned SQLite schema migrations shared across crates
+//!
+//! Several crates open the same SQLite file (e.g. `~/.binks/conversations.db`)
+//! and each owns a subset of its tables. This module gives every crate an
+//! ordered list of [`Migration`]s tracked under its own namespace in a shared
+//! `mcp_schema_migrations` table, so one crate upgrading its tables never
+//! re-runs or clobbers another crate's schema.
+//!
+//! Before applying anything, the [`Migrator`] checks that:
+//!
+//! - migrations are numbered `1..=n` with no gaps or duplicates
+//! - every already-applied migration still has the same SQL (checksum match)
+//! - the database was not migrated by a newer binary with unknown versions
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use mcp_common::migrations::{Migration, Migrator, DEFAULT_BUSY_TIMEOUT};
+//!
+//! const MIGRATIONS: &[Migration] = &[
+//!     Migration::new(1, "create notes table", "CREATE TABLE notes (id TEXT PRIMARY KEY);"),
+//!     Migration::new(2, "add notes.body", "ALTER TABLE notes ADD COLUMN body TEXT;"),
+//! ];
+//!
+//! let conn = rusqlite::Connection::open("notes.db")?;
+//! conn.busy_timeout(DEFAULT_BUSY_TIMEOUT)?;
+//! let version = Migrator::new("notes-mcp", MIGRATIONS).run(&conn)?;
+//! ```
+
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+use std::time::Duration;
+
+/// Table used to record applied migrations for every namespace
+///
+/// Prefixed so it cannot collide with a generic `schema_migrations` table
+/// owned by another tool sharing the same database file.
+pub const MIGRATIONS_TABLE: &str = "mcp_schema_migrations";
+
+/// Columns the migrations table must have
+const MIGRATIONS_COLUMNS: &[&str] = &[
+    "namespace",
+    "version",
+    "description",
+    "checksum",
+    "applied_at",
+];
+
+/// Busy timeout to set on connections to shared databases before migrating
+///
+/// Long enough to wait out another process applying migrations, so startup
+/// does not fail with `SQLITE_BUSY` when several servers launch together.
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error type for migration operations
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// Migration list is not numbered sequentially from 1
+    #[error(
+        "{namespace}: migration at position {position} has version {found}, expected {expected}"
+    )]
+    OutOfOrder {
+        namespace: String,
+        position: usize,
+        expected: u32,
+        found: u32,
+    },
+
+    /// An applied migration's SQL no longer matches the recorded checksum
+    #[error(
+        "{namespace}: migration {version} was modified after being applied (checksum mismatch)"
+    )]
+    ChecksumMismatch { namespace: String, version: u32 },
+
+    /// The database has migrations this binary does not know about
+    #[error(
+        "{namespace}: database is at version {applied}, but only {known} migrations are known"
+    )]
+    UnknownVersion {
+        namespace: String,
+        applied: u32,
+        known: u32,
+    },
+
+    /// A migration failed to apply
+    #[error("{namespace}: migration {version} ({description}) failed: {source}")]
+    Failed {
+        namespace: String,
+        version: u32,
+        description: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+
+    /// The migrations table exists but was not created by this module
+    #[error("table {table} exists with an incompatible layout (missing columns: {missing})")]
+    IncompatibleTable { table: String, missing: String },
+
+    /// Underlying SQLite error
+    #[error("sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+/// Result type for migration operations
+pub type MigrationResult<T> = Result<T, MigrationError>;
+
+/// A single schema migration
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Sequential version number, starting at 1
+    pub version: u32,
+    /// Short human-readable description
+    pub description: &'static str,
+    /// SQL batch to execute
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// Create a new migration
+    pub const fn new(version: u32, description: &'static str, sql: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            sql,
+        }
+    }
+
+    /// Stable checksum of the migration SQL (FNV-1a, hex encoded)
+    ///
+    /// Whitespace at the start and end of each line is ignored so that
+    /// re-indenting a migration does not count as modifying it.
+    pub fn checksum(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for line in self.sql.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            for byte in line.bytes().chain(std::iter::once(b'\n')) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+/// Applies a namespace's migrations to a connection
+#[derive(Debug, Clone, Copy)]
+pub struct Migrator {
+    namespace: &'static str,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    /// Create a migrator for `namespace` (usually the crate name)
+    pub const fn new(namespace: &'static str, migrations: &'static [Migration]) -> Self {
+        Self {
+            namespace,
+            migrations,
+        }
+    }
+
+    /// Version of the latest known migration
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.last().map(|m| m.version).unwrap_or(0)
+    }
+
+    /// Highest migration version applied to the database for this namespace
+    ///
+    /// Read-only: returns 0 if the migrations table does not exist yet.
+    pub fn current_version(&self, conn: &Connection) -> MigrationResult<u32> {
+        if !Self::table_exists(conn)? {
+            return Ok(0);
+        }
+        Self::check_table(conn)?;
+        let version: Option<u32> = conn.query_row(
+            &format!(
+                "SELECT MAX(version) FROM {} WHERE namespace = ?1",
+                MIGRATIONS_TABLE
+            ),
+            params![self.namespace],
+            |row| row.get(0),
+        )?;
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Verify integrity and apply all pending migrations
+    ///
+    /// An up-to-date database is only read, so the common startup path never
+    /// waits on another writer. Otherwise each migration runs in its own
+    /// `IMMEDIATE` transaction together with its bookkeeping row, so a
+    /// failure leaves the database at the last successfully applied version.
+    /// The applied rows are re-read after the write lock is taken, which makes
+    /// concurrent migrators of the same database serialize instead of
+    /// applying the same migration twice.
+    ///
+    /// Callers sharing a database with other processes should set
+    /// [`DEFAULT_BUSY_TIMEOUT`] on `conn` first; otherwise a process that
+    /// finds the database locked fails immediately with `SQLITE_BUSY`.
+    ///
+    /// Returns the resulting version.
+    pub fn run(&self, conn: &Connection) -> MigrationResult<u32> {
+        self.validate()?;
+
+        if Self::table_exists(conn)? {
+            Self::check_table(conn)?;
+            if self.verify_applied(conn)? == self.latest_version() {
+                return Ok(self.latest_version());
+            }
+        }
+
+        loop {
+            let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+            Self::ensure_table(&tx)?;
+            let current = self.verify_applied(&tx)?;
+
+            let Some(migration) = self.migrations.iter().find(|m| m.version > current) else {
+                tx.commit()?;
+                return Ok(self.latest_version());
+            };
+
+            self.apply(&tx, migration)
+                .and_then(|()| tx.commit())
+                .map_err(|source| MigrationError::Failed {
+                    namespace: self.namespace.to_string(),
+                    version: migration.version,
+                    description: migration.description.to_string(),
+                    source,
+                })?;
+            tracing::info!(
+                namespace = self.namespace,
+                version = migration.version,
+                "Applied migration: {}",
+                migration.description
+            );
+        }
+    }
+
+    /// Check applied migrations against the known list and return the
+    /// highest applied version
+    fn verify_applied(&self, conn: &Connection) -> MigrationResult<u32> {
+        let applied = self.applied(conn)?;
+        let known = self.latest_version();
+        for (version, checksum) in &applied {
+            let Some(migration) = self.migrations.iter().find(|m| m.version == *version) else {
+                return Err(MigrationError::UnknownVersion {
+                    namespace: self.namespace.to_string(),
+                    applied: *version,
+                    known,
+                });
+            };
+            if migration.checksum() != *checksum {
+                return Err(MigrationError::ChecksumMismatch {
+                    namespace: self.namespace.to_string(),
+                    version: *version,
+                });
+            }
+        }
+
+        Ok(applied.iter().map(|(v, _)| *v).max().unwrap_or(0))
+    }
+
+    /// Check that migrations are numbered sequentially from 1
+    fn validate(&self) -> MigrationResult<()> {
+        for (position, migration) in self.migrations.iter().enumerate() {
+            let expected = position as u32 + 1;
+            if migration.version != expected {
+                return Err(MigrationError::OutOfOrder {
+                    namespace: self.namespace.to_string(),
+                    position,
+                    expected,
+                    found: migration.version,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Applied (version, checksum) pairs for this namespace
+    fn applied(&self, conn: &Connection) -> MigrationResult<Vec<(u32, String)>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT version, checksum FROM {} WHERE namespace = ?1 ORDER BY version",
+            MIGRATIONS_TABLE
+        ))?;
+        let rows = stmt
+            .query_map(params![self.namespace], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Execute a migration and record it; the caller owns the transaction
+    fn apply(&self, conn: &Connection, migration: &Migration) -> rusqlite::Result<()> {
+        conn.execute_batch(migration.sql)?;
+        conn.execute(
+            &format!(
+                "INSERT INTO {} (namespace, version, description, checksum, applied_at)
+                 VALUES (?1, ?2, ?3, ?4, datetime('now'))",
+                MIGRATIONS_TABLE
+            ),
+            params![
+                self.namespace,
+                migration.version,
+                migration.description,
+                migration.checksum()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn table_exists(conn: &Connection) -> rusqlite::Result<bool> {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            params![MIGRATIONS_TABLE],
+            |row| row.get(0),
+        )
+    }
+
+    /// Reject a pre-existing migrations table with a different layout
+    fn check_table(conn: &Connection) -> MigrationResult<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", MIGRATIONS_TABLE))?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let missing: Vec<&str> = MIGRATIONS_COLUMNS
+            .iter()
+            .copied()
+            .filter(|c| !columns.iter().any(|col| col == c))
+            .collect();
+        if !missing.is_empty() {
+            return Err(MigrationError::IncompatibleTable {
+                table: MIGRATIONS_TABLE.to_string(),
+                missing: missing.join(", "),
+            });
+        }
+        Ok(())
+    }
+
+    fn ensure_table(conn: &Connection) -> MigrationResult<()> {
+        conn.execute_batch(&format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {} (
+                namespace TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                description TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at TEXT NOT NULL,
+                PRIMARY KEY (namespace, version)
+            );
+            "#,
+            MIGRATIONS_TABLE
+        ))?;
+        Self::check_table(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: Migration = Migration::new(1, "create notes", "CREATE TABLE notes (id TEXT);");
+    const V2: Migration = Migration::new(2, "add body", "ALTER TABLE notes ADD COLUMN body TEXT;");
+
+    #[test]
+    fn test_run_applies_pending_migrations() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        let migrator = Migrator::new("notes", &[V1]);
+        assert_eq!(migrator.run(&conn).unwrap(), 1);
+
+        let migrator = Migrator::new("notes", &[V1, V2]);
+        assert_eq!(migrator.run(&conn).unwrap(), 2);
+        assert_eq!(migrator.current_version(&conn).unwrap(), 2);
+
+        // Re-running is a no-op
+        assert_eq!(migrator.run(&conn).unwrap(), 2);
+        conn.execute("INSERT INTO notes (id, body) VALUES ('a', 'b')", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_namespaces_are_isolated() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrator::new("notes", &[V1, V2]).run(&conn).unwrap();
+
+        const OTHER: Migration = Migration::new(1, "create other", "CREATE TABLE other (id TEXT);");
+        assert_eq!(Migrator::new("other", &[OTHER]).run(&conn).unwrap(), 1);
+        assert_eq!(
+            Migrator::new("notes", &[V1, V2])
+                .current_version(&conn)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_modified_migration_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrator::new("notes", &[V1]).run(&conn).unwrap();
+
+        const CHANGED: Migration =
+            Migration::new(1, "create notes", "CREATE TABLE notes (id INTEGER);");
+        let err = Migrator::new("notes", &[CHANGED]).run(&conn).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::ChecksumMismatch { version: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_unknown_version_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrator::new("notes", &[V1, V2]).run(&conn).unwrap();
+
+        let err = Migrator::new("notes", &[V1]).run(&conn).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::UnknownVersion { applied: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_out_of_order_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        let err = Migrator::new("notes", &[V2, V1]).run(&conn).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::OutOfOrder { position: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        const BAD: Migration = Migration::new(2, "broken", "CREATE TABLE x (id TEXT); NOT SQL;");
+        let err = Migrator::new("notes", &[V1, BAD]).run(&conn).unwrap_err();
+        assert!(matches!(err, MigrationError::Failed { version: 2, .. }));
+
+        let migrator = Migrator::new("notes", &[V1]);
+        assert_eq!(migrator.current_version(&conn).unwrap(), 1);
+        let has_x: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'x'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_x);
+    }
+
+    #[test]
+    fn test_second_connection_is_noop() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shared.db");
+        let first = Connection::open(&db_path).unwrap();
+        let second = Connection::open(&db_path).unwrap();
+
+        let migrator = Migrator::new("notes", &[V1, V2]);
+        assert_eq!(migrator.run(&first).unwrap(), 2);
+        assert_eq!(migrator.run(&second).unwrap(), 2);
+
+        let rows: u32 = second
+            .query_row(
+                "SELECT COUNT(*) FROM mcp_schema_migrations WHERE namespace = 'notes'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn test_concurrent_runs_serialize() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shared.db");
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let db_path = db_path.clone();
+                std::thread::spawn(move || {
+                    let conn = Connection::open(&db_path).unwrap();
+                    conn.busy_timeout(std::time::Duration::from_secs(5))
+                        .unwrap();
+                    Migrator::new("notes", &[V1, V2]).run(&conn)
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn test_up_to_date_run_does_not_take_write_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shared.db");
+        let migrator = Migrator::new("notes", &[V1, V2]);
+
+        let conn = Connection::open(&db_path).unwrap();
+        migrator.run(&conn).unwrap();
+
+        // Another writer holds the lock; no busy timeout on `conn`, so taking
+        // the write lock here would fail immediately with SQLITE_BUSY
+        let writer = Connection::open(&db_path).unwrap();
+        writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+        assert_eq!(migrator.run(&conn).unwrap(), 2);
+        assert_eq!(migrator.current_version(&conn).unwrap(), 2);
+        writer.execute_batch("ROLLBACK").unwrap();
+    }
+
+    #[test]
+    fn test_current_version_does_not_create_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(
+            Migrator::new("notes", &[V1])
+                .current_version(&conn)
+                .unwrap(),
+            0
+        );
+        assert!(!Migrator::table_exists(&conn).unwrap());
+    }
+
+    #[test]
+    fn test_foreign_migrations_table_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {} (id INTEGER PRIMARY KEY, name TEXT);",
+            MIGRATIONS_TABLE
+        ))
+        .unwrap();
+
+        let migrator = Migrator::new("notes", &[V1]);
+        let err = migrator.run(&conn).unwrap_err();
+        assert!(matches!(err, MigrationError::IncompatibleTable { .. }));
+        let err = migrator.current_version(&conn).unwrap_err();
+        assert!(matches!(err, MigrationError::IncompatibleTable { .. }));
+    }
+
+    #[test]
+    fn test_checksum_ignores_indentation() {
+        let a = Migration::new(1, "a", "CREATE TABLE t (\n  id TEXT\n);");
+        let b = Migration::new(1, "a", "    CREATE TABLE t (\n        id TEXT\n    );\n");
+        assert_eq!(a.checksum(), b.checksum());
+    }
+}
@@ -1,6 +1,7 @@
 //! Persistent memory layer - SQLite-backed long-term storage
 
 use chrono::Utc;
+use mcp_common::migrations::{Migration, MigrationError, Migrator, DEFAULT_BUSY_TIMEOUT};
 use rusqlite::{params, Connection, Result as SqliteResult};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -9,6 +10,79 @@ use uuid::Uuid;
 
 use crate::types::{Entity, EntityWithFacts, Fact, Relation, Summary};
 
+/// Ordered memory-mcp migrations, tracked under the `memory-mcp` namespace
+pub const MIGRATIONS: &[Migration] = &[Migration::new(
+    1,
+    "create entities, facts, relations and summaries",
+    r#"
+        -- Entities table
+        CREATE TABLE IF NOT EXISTS entities (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            entity_type TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_entities_name ON entities(name);
+        CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type);
+
+        -- Facts table
+        CREATE TABLE IF NOT EXISTS facts (
+            id TEXT PRIMARY KEY,
+            entity_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            source TEXT NOT NULL,
+            confidence REAL NOT NULL,
+            recorded_at TEXT NOT NULL,
+            FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_facts_entity ON facts(entity_id);
+        CREATE INDEX IF NOT EXISTS idx_facts_key ON facts(key);
+
+        -- Relations table
+        CREATE TABLE IF NOT EXISTS relations (
+            id TEXT PRIMARY KEY,
+            from_entity_id TEXT NOT NULL,
+            to_entity_id TEXT NOT NULL,
+            relation_type TEXT NOT NULL,
+            recorded_at TEXT NOT NULL,
+            FOREIGN KEY (from_entity_id) REFERENCES entities(id) ON DELETE CASCADE,
+            FOREIGN KEY (to_entity_id) REFERENCES entities(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_relations_from ON relations(from_entity_id);
+        CREATE INDEX IF NOT EXISTS idx_relations_to ON relations(to_entity_id);
+        CREATE INDEX IF NOT EXISTS idx_relations_type ON relations(relation_type);
+
+        -- Session summaries table
+        CREATE TABLE IF NOT EXISTS summaries (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            thought_count INTEGER NOT NULL,
+            session_start TEXT NOT NULL,
+            session_end TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_summaries_session ON summaries(session_id);
+        "#,
+)];
+
+/// Migrator for the persistent memory schema
+pub const MIGRATOR: Migrator = Migrator::new("memory-mcp", MIGRATIONS);
+
+/// Error opening the persistent memory database
+#[derive(Debug, thiserror::Error)]
+pub enum PersistentError {
+    /// Opening or configuring the database failed
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    /// Bringing the schema up to date failed
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+}
+
 /// Persistent memory - SQLite-backed knowledge graph
 #[derive(Clone)]
 pub struct PersistentMemory {
@@ -17,13 +91,14 @@ pub struct PersistentMemory {
 
 impl PersistentMemory {
     /// Create a new persistent memory with the given database path
-    pub fn new(db_path: PathBuf) -> SqliteResult<Self> {
+    pub fn new(db_path: PathBuf) -> Result<Self, PersistentError> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).ok();
         }
 
         let conn = Connection::open(&db_path)?;
+        conn.busy_timeout(DEFAULT_BUSY_TIMEOUT)?;
         let memory = Self {
             conn: Arc::new(Mutex::new(conn)),
         };
@@ -34,75 +109,17 @@ impl PersistentMemory {
             // We're in an async context, need to spawn blocking
             tokio::task::block_in_place(|| {
                 let conn = memory.conn.blocking_lock();
-                Self::init_schema_sync(&conn)
+                MIGRATOR.run(&conn)
             })?;
         } else {
             // We're not in async context, just run directly
             let conn = memory.conn.blocking_lock();
-            Self::init_schema_sync(&conn)?;
+            MIGRATOR.run(&conn)?;
         }
 
         Ok(memory)
     }
 
-    /// Initialize the database schema (synchronous version for startup)
-    fn init_schema_sync(conn: &Connection) -> SqliteResult<()> {
-        conn.execute_batch(
-            r#"
-            -- Entities table
-            CREATE TABLE IF NOT EXISTS entities (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL UNIQUE,
-                entity_type TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_entities_name ON entities(name);
-            CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type);
-
-            -- Facts table
-            CREATE TABLE IF NOT EXISTS facts (
-                id TEXT PRIMARY KEY,
-                entity_id TEXT NOT NULL,
-                key TEXT NOT NULL,
-                value TEXT NOT NULL,
-                source TEXT NOT NULL,
-                confidence REAL NOT NULL,
-                recorded_at TEXT NOT NULL,
-                FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_facts_entity ON facts(entity_id);
-            CREATE INDEX IF NOT EXISTS idx_facts_key ON facts(key);
-
-            -- Relations table
-            CREATE TABLE IF NOT EXISTS relations (
-                id TEXT PRIMARY KEY,
-                from_entity_id TEXT NOT NULL,
-                to_entity_id TEXT NOT NULL,
-                relation_type TEXT NOT NULL,
-                recorded_at TEXT NOT NULL,
-                FOREIGN KEY (from_entity_id) REFERENCES entities(id) ON DELETE CASCADE,
-                FOREIGN KEY (to_entity_id) REFERENCES entities(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_relations_from ON relations(from_entity_id);
-            CREATE INDEX IF NOT EXISTS idx_relations_to ON relations(to_entity_id);
-            CREATE INDEX IF NOT EXISTS idx_relations_type ON relations(relation_type);
-
-            -- Session summaries table
-            CREATE TABLE IF NOT EXISTS summaries (
-                id TEXT PRIMARY KEY,
-                session_id TEXT NOT NULL,
-                content TEXT NOT NULL,
-                thought_count INTEGER NOT NULL,
-                session_start TEXT NOT NULL,
-                session_end TEXT NOT NULL,
-                created_at TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_summaries_session ON summaries(session_id);
-            "#,
-        )
-    }
-
     // ========================================================================
     // Entity Operations
     // ========================================================================
@@ -502,6 +519,34 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_open_existing_database_without_migration_rows() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("memory.db");
+
+        // A memory.db written before migrations existed
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+            conn.execute(
+                "INSERT INTO entities (id, name, entity_type, created_at, updated_at)
+                 VALUES ('e1', 'test:existing', 'project', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let memory = PersistentMemory::new(db_path.clone()).unwrap();
+        let entity = memory.get_entity("test:existing").await.unwrap().unwrap();
+        assert_eq!(entity.id, "e1");
+
+        let conn = Connection::open(&db_path).unwrap();
+        assert_eq!(
+            MIGRATOR.current_version(&conn).unwrap(),
+            MIGRATOR.latest_version()
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_entity_lifecycle() {
         let dir = tempdir().unwrap();
@@ -1,6 +1,7 @@
 //! Task repository for shared database access
 
 use anyhow::{Context, Result};
+use mcp_common::migrations::DEFAULT_BUSY_TIMEOUT;
 use rusqlite::{params, types::FromSqlError, Connection, OptionalExtension};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -44,6 +45,10 @@ impl TaskRepository {
         let conn = Connection::open(&db_path)
             .with_context(|| format!("Failed to open database at {:?}", db_path))?;
 
+        // The database is shared with the agent and other task-mcp instances;
+        // wait for their migrations instead of failing with SQLITE_BUSY
+        conn.busy_timeout(DEFAULT_BUSY_TIMEOUT)?;
+
         // Ensure schema exists
         schema::ensure_tables(&conn)?;
 
@@ -1,16 +1,18 @@
 //! Database schema initialization for task-mcp
 //!
 //! This module ensures the required tables exist in the shared database.
-//! The schema is defined in agent/src/db/schema.rs and is shared with the agent.
+//! The tables are shared with the agent, so every statement in the initial
+//! migration is idempotent; later schema changes go in new migrations.
 
 use anyhow::Result;
+use mcp_common::migrations::{Migration, Migrator};
 use rusqlite::Connection;
 
-/// Ensure task-related tables exist
-/// This is a minimal check - full schema is managed by the agent
-pub fn ensure_tables(conn: &Connection) -> Result<()> {
-    conn.execute_batch(
-        r#"
+/// Ordered task-mcp migrations, tracked under the `task-mcp` namespace
+pub const MIGRATIONS: &[Migration] = &[Migration::new(
+    1,
+    "create tasks, task_dependencies and task_executions",
+    r#"
         -- Tasks table
         CREATE TABLE IF NOT EXISTS tasks (
             id TEXT PRIMARY KEY,
@@ -65,7 +67,13 @@ pub fn ensure_tables(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_task_executions_task
         ON task_executions(task_id, started_at DESC);
         "#,
-    )?;
+)];
+
+/// Migrator for task-related tables
+pub const MIGRATOR: Migrator = Migrator::new("task-mcp", MIGRATIONS);
 
+/// Ensure task-related tables exist and are at the latest schema version
+pub fn ensure_tables(conn: &Connection) -> Result<()> {
+    MIGRATOR.run(conn)?;
     Ok(())
 }
@@ -242,4 +242,35 @@ mod tests {
         let tasks = repo.list_tasks(filter).unwrap();
         assert_eq!(tasks.len(), 20);
     }
+
+    #[test]
+    fn test_open_existing_database_without_migration_rows() {
+        use super::super::schema::{MIGRATIONS, MIGRATOR};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("conversations.db");
+
+        // A conversations.db from before migrations: tables already created
+        // (by the agent or an older task-mcp) and holding data
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+            conn.execute(
+                "INSERT INTO tasks (id, title, description, status, created_at)
+                 VALUES ('11111111-aaaa', 'Existing', 'From before migrations', 'pending', '2026-01-01T00:00:00Z')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let repo = TaskRepository::new(db_path.clone()).unwrap();
+        let task = repo.get_task("11111111-aaaa").unwrap().unwrap();
+        assert_eq!(task.title, "Existing");
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        assert_eq!(
+            MIGRATOR.current_version(&conn).unwrap(),
+            MIGRATOR.latest_version()
+        );
+    }
 }